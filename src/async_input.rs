@@ -2,11 +2,11 @@
 ///
 /// This module provides an asynchronous version of the `input!` macro,
 /// implemented as the `input_async!` macro, which allows collecting and validating user input
-/// in asynchronous contexts (e.g., with Tokio).
+/// in asynchronous contexts (e.g., with Tokio or async-std).
 ///
 /// ## Features
 ///
-/// - Asynchronous input handling using `tokio::io`.
+/// - Asynchronous input handling via the [runtime abstraction](crate::runtime).
 /// - Supports types implementing the `FromStr` trait.
 /// - Gracefully handles invalid input by repeatedly prompting the user.
 /// - Optional custom error handler.
@@ -15,7 +15,7 @@
 ///
 /// Basic usage:
 ///
-/// ```no_run
+/// ```ignore
 /// use macro_input::input_async;
 ///
 /// #[tokio::main]
@@ -28,7 +28,7 @@
 ///
 /// With a custom error handler:
 ///
-/// ```no_run
+/// ```ignore
 /// use macro_input::input_async;
 ///
 /// #[tokio::main]
@@ -45,18 +45,383 @@
 ///
 /// ## Notes
 ///
-/// - Requires the Tokio runtime (version 1.45 or later).
-/// - Only supports `stdin` input via `tokio::io::stdin()`.
+/// - Requires the `rt-tokio` or `rt-async-std` Cargo feature to be enabled.
+/// - Reads from the selected runtime's `stdin()`, except in the
+///   `from: reader, to: writer` form, which uses the caller's streams.
 /// - The input type must implement `FromStr`.
+use crate::runtime::{self, stdin, AsyncBufReadExt, BufReader, Racing, Stdin};
+use std::time::Duration;
 
+/// A reusable, timeout-aware wrapper around the selected runtime's stdin.
+///
+/// Unlike calling `stdin()` fresh each time, `AsyncInput` keeps a single
+/// [`BufReader`] alive across calls, so a timed-out read doesn't lose
+/// whatever the OS has already buffered for the next call.
+///
+/// # Examples
+///
+/// ```ignore
+/// use macro_input::async_input::AsyncInput;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut input = AsyncInput::new();
+///     match input.read_line().await {
+///         Ok(Some(line)) => println!("You entered: {}", line),
+///         Ok(None) => println!("Input stream closed (EOF)."),
+///         Err(e) => eprintln!("Input error: {}", e),
+///     }
+/// }
+/// ```
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "rt-tokio", feature = "rt-async-std")))
+)]
+pub struct AsyncInput {
+    reader: BufReader<Stdin>,
+}
+
+impl AsyncInput {
+    /// Creates a new `AsyncInput` backed by the selected runtime's `stdin()`.
+    pub fn new() -> Self {
+        Self {
+            reader: BufReader::new(stdin()),
+        }
+    }
+
+    /// Reads a single line from stdin, waiting indefinitely for input.
+    ///
+    /// Returns `Ok(None)` on EOF instead of an empty line, so callers can
+    /// tell a closed stream apart from a blank line and stop re-prompting.
+    pub async fn read_line(&mut self) -> std::io::Result<Option<String>> {
+        let mut buffer = String::new();
+        if self.reader.read_line(&mut buffer).await? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(buffer))
+    }
+
+    /// Reads a single line from stdin, giving up once `timeout` elapses.
+    ///
+    /// Races `read_line` against the runtime's `timeout`. On expiry the
+    /// in-progress read is dropped and `Err(TimeoutError)` is returned, but
+    /// the underlying reader is left intact so the next call can keep
+    /// reading from where the OS left off. Returns `Ok(Ok(None))` on EOF
+    /// instead of an empty line, so callers can tell a closed stream apart
+    /// from a blank line and stop re-prompting.
+    pub async fn read_line_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<std::io::Result<Option<String>>, runtime::TimeoutError> {
+        let reader = &mut self.reader;
+        runtime::timeout(timeout, async {
+            let mut buffer = String::new();
+            if reader.read_line(&mut buffer).await? == 0 {
+                return Ok(None);
+            }
+            Ok(Some(buffer))
+        })
+        .await
+    }
+}
+
+impl Default for AsyncInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The result of [`AsyncInput::read_line_or`]: either a completed line,
+/// notice that the caller-supplied cancellation future resolved first, or
+/// that stdin hit EOF.
+#[derive(Debug)]
+pub enum ReadLineOutcome {
+    /// A full line was read from stdin.
+    Line(String),
+    /// The cancellation future completed before a line was available.
+    Cancelled,
+    /// stdin hit EOF before a line was available.
+    Eof,
+}
+
+impl AsyncInput {
+    /// Reads a line from stdin, or bails out early if `cancel` resolves first.
+    ///
+    /// Mirrors the `futures::select!` pattern of merging a `stdin.lines()`
+    /// stream with another stream (e.g. messages from a server): both futures
+    /// are driven concurrently via [`runtime::race`], and whichever resolves
+    /// first wins. If `cancel` wins, the half-read line is dropped cleanly
+    /// and the reader stays intact for the next call.
+    pub async fn read_line_or<F>(&mut self, cancel: F) -> std::io::Result<ReadLineOutcome>
+    where
+        F: std::future::Future<Output = ()>,
+    {
+        let reader = &mut self.reader;
+        let read = async {
+            let mut buffer = String::new();
+            if reader.read_line(&mut buffer).await? == 0 {
+                return Ok::<_, std::io::Error>(None);
+            }
+            Ok(Some(buffer))
+        };
+        match runtime::race(read, cancel).await {
+            Racing::Left(result) => result.map(|line| match line {
+                Some(line) => ReadLineOutcome::Line(line),
+                None => ReadLineOutcome::Eof,
+            }),
+            Racing::Right(()) => Ok(ReadLineOutcome::Cancelled),
+        }
+    }
+}
+
+/// Async equivalent of [`read_input`](crate::read_input): runs the
+/// prompt-parse-retry loop against caller-supplied async streams.
+///
+/// Exposed so a test can drive it with an in-memory reader (e.g.
+/// `BufReader::new(Cursor::new(b"..."))`) and a `Vec<u8>` writer instead of
+/// the real stdin/stdout. See [`read_input`](crate::read_input) for what
+/// `validate` and `max_attempts` do.
+pub async fn read_input_async<R, W, T, F, V>(
+    reader: &mut R,
+    writer: &mut W,
+    desc: &str,
+    mut on_error: Option<F>,
+    mut validate: Option<V>,
+    max_attempts: Option<usize>,
+) -> Result<T, crate::InputError>
+where
+    R: AsyncBufReadExt + Unpin,
+    W: runtime::AsyncWriteExt + Unpin,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+    F: FnMut(&T::Err),
+    V: FnMut(&T) -> Result<(), String>,
+{
+    let mut attempts = 0usize;
+    loop {
+        writer
+            .write_all(format!("{} ({}): ", desc, std::any::type_name::<T>()).as_bytes())
+            .await
+            .unwrap();
+        writer.flush().await.unwrap();
+
+        let mut buffer = String::new();
+        match reader.read_line(&mut buffer).await {
+            Ok(0) => return Err(crate::InputError::Eof),
+            Err(_) => continue,
+            Ok(_) => {}
+        }
+        attempts += 1;
+
+        let buffer = buffer.trim();
+        match buffer.parse::<T>() {
+            Ok(val) => match validate.as_mut().map(|validate| validate(&val)) {
+                None | Some(Ok(())) => return Ok(val),
+                Some(Err(msg)) => {
+                    writer
+                        .write_all(format!("Invalid input '{}'. {}\n", buffer, msg).as_bytes())
+                        .await
+                        .unwrap();
+                }
+            },
+            Err(err) => {
+                writer
+                    .write_all(
+                        format!(
+                            "Invalid input '{}'. Expected type. Error: {}\n",
+                            buffer, err
+                        )
+                        .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+                if let Some(f) = on_error.as_mut() {
+                    f(&err);
+                }
+            }
+        }
+
+        if let Some(max_attempts) = max_attempts {
+            if attempts >= max_attempts {
+                return Err(crate::InputError::MaxAttemptsExceeded { attempts });
+            }
+        }
+    }
+}
+
+/// # Examples
+///
+/// The `validate:` and `max_attempts:` arms, exercised as real macro
+/// invocations (compile-checked by `cargo test --doc`, but never called, so
+/// it never actually touches stdin):
+///
+/// ```
+/// use macro_input::input_async;
+///
+/// async fn compiles() {
+///     let mut number: i32 = 0;
+///     input_async!(number, "Enter a number", i32; max_attempts: 3);
+///     input_async!(number, "Enter a positive number", i32; validate: |n: &i32| {
+///         if *n > 0 { Ok(()) } else { Err("must be positive".to_string()) }
+///     }, max_attempts: 3);
+/// }
+///
+/// fn main() {}
+/// ```
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "rt-tokio", feature = "rt-async-std")))
+)]
 #[macro_export]
 macro_rules! input_async {
+    ($field:expr, $desc:expr, $ty:ty; from: $reader:expr, to: $writer:expr) => {{
+        $field = $crate::async_input::read_input_async::<
+            _,
+            _,
+            $ty,
+            fn(&_),
+            fn(&$ty) -> Result<(), String>,
+        >($reader, $writer, $desc, None, None, None)
+        .await
+        .expect("input stream closed unexpectedly");
+    }};
+
+    ($field:expr, $desc:expr, $ty:ty; validate: $validate:expr, max_attempts: $max:expr) => {{
+        let mut reader = $crate::runtime::BufReader::new($crate::runtime::stdin());
+        let mut stdout = $crate::runtime::stdout();
+        $field = $crate::async_input::read_input_async::<_, _, $ty, fn(&_), _>(
+            &mut reader,
+            &mut stdout,
+            $desc,
+            None,
+            Some($validate),
+            Some($max),
+        )
+        .await
+        .expect("input stream closed unexpectedly");
+    }};
+
+    ($field:expr, $desc:expr, $ty:ty; validate: $validate:expr) => {{
+        let mut reader = $crate::runtime::BufReader::new($crate::runtime::stdin());
+        let mut stdout = $crate::runtime::stdout();
+        $field = $crate::async_input::read_input_async::<_, _, $ty, fn(&_), _>(
+            &mut reader,
+            &mut stdout,
+            $desc,
+            None,
+            Some($validate),
+            None,
+        )
+        .await
+        .expect("input stream closed unexpectedly");
+    }};
+
+    ($field:expr, $desc:expr, $ty:ty; max_attempts: $max:expr) => {{
+        let mut reader = $crate::runtime::BufReader::new($crate::runtime::stdin());
+        let mut stdout = $crate::runtime::stdout();
+        $field = $crate::async_input::read_input_async::<
+            _,
+            _,
+            $ty,
+            fn(&_),
+            fn(&$ty) -> Result<(), String>,
+        >(&mut reader, &mut stdout, $desc, None, None, Some($max))
+        .await
+        .expect("input stream closed unexpectedly");
+    }};
+
+    ($field:expr, $desc:expr, $ty:ty, cancel: $cancel:expr) => {{
+        use std::str::FromStr;
+        use $crate::async_input::{AsyncInput, ReadLineOutcome};
+
+        let mut __input_async_reader = AsyncInput::new();
+
+        loop {
+            print!("{} ({}): ", $desc, stringify!($ty));
+            use std::io::Write;
+            std::io::stdout().flush().unwrap();
+
+            match __input_async_reader.read_line_or($cancel).await {
+                Ok(ReadLineOutcome::Line(buffer)) => {
+                    let input = buffer.trim();
+                    match <$ty>::from_str(input) {
+                        Ok(val) => {
+                            $field = val;
+                            break;
+                        }
+                        Err(e) => {
+                            eprintln!(
+                                "Invalid input '{}'. Expected type: {}. Error: {}",
+                                input,
+                                stringify!($ty),
+                                e
+                            );
+                        }
+                    }
+                }
+                Ok(ReadLineOutcome::Cancelled) => {
+                    eprintln!("Input cancelled.");
+                    break;
+                }
+                Ok(ReadLineOutcome::Eof) => {
+                    eprintln!("Input stream closed (EOF).");
+                    break;
+                }
+                Err(err) => {
+                    eprintln!("Input read error: {}", err);
+                }
+            }
+        }
+    }};
+
+    ($field:expr, $desc:expr, $ty:ty, timeout = $timeout:expr) => {{
+        use std::str::FromStr;
+        use $crate::async_input::AsyncInput;
+
+        let mut __input_async_reader = AsyncInput::new();
+
+        loop {
+            print!("{} ({}): ", $desc, stringify!($ty));
+            use std::io::Write;
+            std::io::stdout().flush().unwrap();
+
+            match __input_async_reader.read_line_timeout($timeout).await {
+                Ok(Ok(Some(buffer))) => {
+                    let input = buffer.trim();
+                    match <$ty>::from_str(input) {
+                        Ok(val) => {
+                            $field = val;
+                            break;
+                        }
+                        Err(e) => {
+                            eprintln!(
+                                "Invalid input '{}'. Expected type: {}. Error: {}",
+                                input,
+                                stringify!($ty),
+                                e
+                            );
+                        }
+                    }
+                }
+                Ok(Ok(None)) => {
+                    eprintln!("Input stream closed (EOF).");
+                    break;
+                }
+                Ok(Err(err)) => {
+                    eprintln!("Input read error: {}", err);
+                }
+                Err(_elapsed) => {
+                    eprintln!("Timed out waiting for input after {:?}, please try again.", $timeout);
+                }
+            }
+        }
+    }};
+
     ($field:expr,$desc:expr,$ty:ty,$on_error:expr) => {{
         use std::str::FromStr;
-        use tokio::io::{self, AsyncBufReadExt, BufReader};
+        use $crate::runtime::{stdin, AsyncBufReadExt, BufReader};
 
-        let stdin = io::stdin();
-        let mut reader = BufReader::new(stdin);
+        let mut reader = BufReader::new(stdin());
         let mut buffer = String::new();
 
         loop {
@@ -65,10 +430,16 @@ macro_rules! input_async {
             std::io::stdout().flush().unwrap();
 
             buffer.clear();
-            if let Err(err) = reader.read_line(&mut buffer).await {
-                eprintln!("Input read error: {}", err);
-                $on_error(err);
-                continue;
+            match reader.read_line(&mut buffer).await {
+                Ok(0) => {
+                    eprintln!("Input stream closed (EOF).");
+                    break;
+                }
+                Err(err) => {
+                    eprintln!("Input read error: {}", err);
+                    continue;
+                }
+                Ok(_) => {}
             }
 
             let input = buffer.trim();
@@ -92,10 +463,9 @@ macro_rules! input_async {
 
     ($field:expr, $desc:expr, $ty:ty) => {{
         use std::str::FromStr;
-        use tokio::io::{self, AsyncBufReadExt, BufReader};
+        use $crate::runtime::{stdin, AsyncBufReadExt, BufReader};
 
-        let stdin = io::stdin();
-        let mut reader = BufReader::new(stdin);
+        let mut reader = BufReader::new(stdin());
         let mut buffer = String::new();
 
         loop {
@@ -104,9 +474,16 @@ macro_rules! input_async {
             std::io::stdout().flush().unwrap();
 
             buffer.clear();
-            if let Err(err) = reader.read_line(&mut buffer).await {
-                eprintln!("Input read error: {}", err);
-                continue;
+            match reader.read_line(&mut buffer).await {
+                Ok(0) => {
+                    eprintln!("Input stream closed (EOF).");
+                    break;
+                }
+                Err(err) => {
+                    eprintln!("Input read error: {}", err);
+                    continue;
+                }
+                Ok(_) => {}
             }
 
             let input = buffer.trim();
@@ -127,3 +504,84 @@ macro_rules! input_async {
         }
     }};
 }
+
+#[cfg(all(test, feature = "rt-tokio"))]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[tokio::test]
+    async fn reprompts_on_parse_failure_then_succeeds() {
+        let mut reader = BufReader::new(Cursor::new(b"not a number\n42\n".as_slice()));
+        let mut writer: Vec<u8> = Vec::new();
+        let result = read_input_async::<_, _, i32, fn(&_), fn(&i32) -> Result<(), String>>(
+            &mut reader,
+            &mut writer,
+            "Enter a number",
+            None,
+            None,
+            None,
+        )
+        .await;
+        assert_eq!(result.unwrap(), 42);
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Invalid input"));
+    }
+
+    #[tokio::test]
+    async fn rejects_value_that_fails_validation() {
+        let mut reader = BufReader::new(Cursor::new(b"-1\n5\n".as_slice()));
+        let mut writer: Vec<u8> = Vec::new();
+        let result = read_input_async::<_, _, i32, fn(&_), _>(
+            &mut reader,
+            &mut writer,
+            "Enter a positive number",
+            None,
+            Some(|val: &i32| {
+                if *val > 0 {
+                    Ok(())
+                } else {
+                    Err("must be positive".to_string())
+                }
+            }),
+            None,
+        )
+        .await;
+        assert_eq!(result.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let mut reader = BufReader::new(Cursor::new(b"x\ny\nz\n".as_slice()));
+        let mut writer: Vec<u8> = Vec::new();
+        let result = read_input_async::<_, _, i32, fn(&_), fn(&i32) -> Result<(), String>>(
+            &mut reader,
+            &mut writer,
+            "Enter a number",
+            None,
+            None,
+            Some(3),
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(crate::InputError::MaxAttemptsExceeded { attempts: 3 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn returns_eof_on_closed_stream() {
+        let mut reader = BufReader::new(Cursor::new(b"".as_slice()));
+        let mut writer: Vec<u8> = Vec::new();
+        let result = read_input_async::<_, _, i32, fn(&_), fn(&i32) -> Result<(), String>>(
+            &mut reader,
+            &mut writer,
+            "Enter a number",
+            None,
+            None,
+            None,
+        )
+        .await;
+        assert!(matches!(result, Err(crate::InputError::Eof)));
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
 use std::{
     io::{BufRead, Write},
     str::FromStr,
@@ -22,7 +24,7 @@ extern crate once_cell;
 /// #Examples
 ///
 ///
-/// ```rust
+/// ```no_run
 /// use macro_input::input;
 ///
 /// fn main() {
@@ -50,7 +52,7 @@ extern crate once_cell;
 ///
 /// Example of use with a custom error handler:
 ///
-/// ```rust
+/// ```no_run
 /// use macro_input::input;
 ///
 /// fn main() {
@@ -71,41 +73,172 @@ extern crate once_cell;
 /// # Notes
 /// - The custom handler receives an error object of type `std::num::ParseFloatError`
 //// (or other error type corresponding to the parsed value).
+///
+/// # Examples
+///
+/// The `validate:` and `max_attempts:` arms, exercised as real macro
+/// invocations (compile-checked by `cargo test --doc`, but gated behind
+/// `if false` so it never actually touches stdin):
+///
+/// ```
+/// use macro_input::input;
+///
+/// fn compiles() {
+///     let mut number: i32 = 0;
+///     if false {
+///         input!(number, "Enter a number", i32; max_attempts: 3);
+///         input!(number, "Enter a positive number", i32; validate: |n: &i32| {
+///             if *n > 0 { Ok(()) } else { Err("must be positive".to_string()) }
+///         }, max_attempts: 3);
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! input {
     ($field:expr, $desc:expr, $ty:ty, $on_error:expr) => {{
         let mut stdin = std::io::stdin().lock();
-        let mut stdout = std::io::stdout();
-        $field = $crate::read_input(&mut stdin, &mut stdout, $desc, Some($on_error));
+        let mut stdout = $crate::CapturingStdout;
+        $field = $crate::read_input::<_, $ty, _, fn(&$ty) -> Result<(), String>>(
+            &mut stdin,
+            &mut stdout,
+            $desc,
+            Some(|err: &<$ty as std::str::FromStr>::Err| $on_error(err)),
+            None,
+            None,
+        )
+        .expect("input stream closed unexpectedly");
     }};
     ($field:expr, $desc:expr, $ty:ty) => {{
         let mut stdin = std::io::stdin().lock();
-        let mut stdout = std::io::stdout();
-        $field = $crate::read_input::<_, $ty, _>(&mut stdin, &mut stdout, $desc, None);
+        let mut stdout = $crate::CapturingStdout;
+        $field = $crate::read_input::<_, $ty, fn(&_), fn(&$ty) -> Result<(), String>>(
+            &mut stdin, &mut stdout, $desc, None, None, None,
+        )
+        .expect("input stream closed unexpectedly");
     }};
+    ($field:expr, $desc:expr, $ty:ty; from: $reader:expr, to: $writer:expr) => {{
+        $field = $crate::read_input::<_, $ty, fn(&_), fn(&$ty) -> Result<(), String>>(
+            $reader, $writer, $desc, None, None, None,
+        )
+        .expect("input stream closed unexpectedly");
+    }};
+    ($field:expr, $desc:expr, $ty:ty; validate: $validate:expr) => {{
+        let mut stdin = std::io::stdin().lock();
+        let mut stdout = $crate::CapturingStdout;
+        $field = $crate::read_input::<_, $ty, fn(&_), _>(
+            &mut stdin,
+            &mut stdout,
+            $desc,
+            None,
+            Some($validate),
+            None,
+        )
+        .expect("input stream closed unexpectedly");
+    }};
+    ($field:expr, $desc:expr, $ty:ty; max_attempts: $max:expr) => {{
+        let mut stdin = std::io::stdin().lock();
+        let mut stdout = $crate::CapturingStdout;
+        $field = $crate::read_input::<_, $ty, fn(&_), fn(&$ty) -> Result<(), String>>(
+            &mut stdin,
+            &mut stdout,
+            $desc,
+            None,
+            None,
+            Some($max),
+        )
+        .expect("input stream closed unexpectedly");
+    }};
+    ($field:expr, $desc:expr, $ty:ty; validate: $validate:expr, max_attempts: $max:expr) => {{
+        let mut stdin = std::io::stdin().lock();
+        let mut stdout = $crate::CapturingStdout;
+        $field = $crate::read_input::<_, $ty, fn(&_), _>(
+            &mut stdin,
+            &mut stdout,
+            $desc,
+            None,
+            Some($validate),
+            Some($max),
+        )
+        .expect("input stream closed unexpectedly");
+    }};
+}
+
+/// Why an input loop ([`read_input`] or friends) gave up before producing a
+/// value.
+#[derive(Debug)]
+pub enum InputError {
+    /// `read_line` returned `Ok(0)`: the input stream hit EOF.
+    ///
+    /// Returned regardless of `max_attempts`, since looping forever on a
+    /// closed stream (as the old `read_line`-returns-`Ok(0)` path did) just
+    /// spins instead of making progress.
+    Eof,
+    /// `max_attempts` was reached without a valid, accepted value.
+    MaxAttemptsExceeded {
+        /// How many attempts were made before giving up.
+        attempts: usize,
+    },
 }
 
-pub(crate) fn read_input<R: BufRead, T: FromStr, F: FnMut(&T::Err)>(
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Eof => write!(f, "input stream closed (EOF) before a valid value was read"),
+            InputError::MaxAttemptsExceeded { attempts } => {
+                write!(f, "gave up after {} attempt(s) without valid input", attempts)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Runs the prompt-parse-retry loop against caller-supplied streams.
+///
+/// This is what every `input!` macro expansion calls into, but
+/// it's also `pub` in its own right so a test can drive it directly with a
+/// `Cursor<&[u8]>` reader and a `Vec<u8>` (or any other `Write`) writer,
+/// without touching the real stdin/stdout.
+///
+/// After a successful parse, `validate` (if given) gets a chance to reject
+/// the value on semantic grounds (e.g. "must be in 1..=100"); a rejection is
+/// re-prompted exactly like a parse failure. `max_attempts` (if given) bounds
+/// how many lines will be read before giving up with
+/// [`InputError::MaxAttemptsExceeded`]; `None` retries indefinitely, as
+/// before. Either way, EOF now returns [`InputError::Eof`] instead of
+/// spinning forever on a closed stream.
+pub fn read_input<R: BufRead, T: FromStr, F: FnMut(&T::Err), V: FnMut(&T) -> Result<(), String>>(
     reader: &mut R,
     writer: &mut impl Write,
     desc: &str,
     mut on_error: Option<F>,
-) -> T
+    mut validate: Option<V>,
+    max_attempts: Option<usize>,
+) -> Result<T, InputError>
 where
     T::Err: std::fmt::Display,
 {
+    let mut attempts = 0usize;
     loop {
         write!(writer, "{} ({}): ", desc, std::any::type_name::<T>()).unwrap();
         writer.flush().unwrap();
 
         let mut buffer = String::new();
-        if reader.read_line(&mut buffer).is_err() {
-            if let Some(f) = &on_error {}
-            continue;
+        match reader.read_line(&mut buffer) {
+            Ok(0) => return Err(InputError::Eof),
+            Err(_) => continue,
+            Ok(_) => {}
         }
+        attempts += 1;
+
         let buffer = buffer.trim();
         match buffer.parse::<T>() {
-            Ok(val) => return val,
+            Ok(val) => match validate.as_mut().map(|validate| validate(&val)) {
+                None | Some(Ok(())) => return Ok(val),
+                Some(Err(msg)) => {
+                    writeln!(writer, "Invalid input '{}'. {}", buffer, msg).unwrap();
+                }
+            },
             Err(err) => {
                 writeln!(
                     writer,
@@ -113,11 +246,61 @@ where
                     buffer, err
                 )
                 .unwrap();
-                if let Some(f) = &on_error.as_mut() {
-                    // if read_line return error
+                if let Some(f) = on_error.as_mut() {
+                    f(&err);
                 }
             }
         }
+
+        if let Some(max_attempts) = max_attempts {
+            if attempts >= max_attempts {
+                return Err(InputError::MaxAttemptsExceeded { attempts });
+            }
+        }
+    }
+}
+
+thread_local! {
+    static OUTPUT_CAPTURE: std::cell::RefCell<Option<Vec<u8>>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Installs (or removes) the thread-local output capture buffer, returning
+/// whatever was previously installed.
+///
+/// Modeled on std's internal `OUTPUT_CAPTURE` mechanism (the one `#[test]`
+/// harnesses use to capture `println!` output): while a buffer is installed,
+/// [`CapturingStdout`] writes into it instead of the real stdout. This lets a
+/// test assert on the exact prompts and error messages a macro printed
+/// during an input session.
+pub fn set_output_capture(buf: Option<Vec<u8>>) -> Option<Vec<u8>> {
+    OUTPUT_CAPTURE.with(|cell| std::mem::replace(&mut *cell.borrow_mut(), buf))
+}
+
+/// The [`Write`] target used by `input!` by default.
+///
+/// Writes go to the thread-local buffer installed by [`set_output_capture`]
+/// when one is present, and fall through to the real stdout otherwise.
+pub struct CapturingStdout;
+
+impl Write for CapturingStdout {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let captured = OUTPUT_CAPTURE.with(|cell| {
+            if let Some(captured) = cell.borrow_mut().as_mut() {
+                captured.extend_from_slice(buf);
+                true
+            } else {
+                false
+            }
+        });
+        if captured {
+            Ok(buf.len())
+        } else {
+            std::io::stdout().write(buf)
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stdout().flush()
     }
 }
 
@@ -134,9 +317,9 @@ where
 /// - Integration with popular async runtimes
 ///
 /// # Examples
-/// ```
-/// use crate::async_input::AsyncInput;
-/// 
+/// ```ignore
+/// use macro_input::async_input::AsyncInput;
+///
 /// #[tokio::main]
 /// async fn main() {
 ///     let mut input = AsyncInput::new();
@@ -149,37 +332,137 @@ where
 ///
 /// # Safety
 /// All operations are thread-safe when used with proper async runtime.
+///
+/// # Feature flags
+/// Requires either the `rt-tokio` or `rt-async-std` Cargo feature; neither
+/// is enabled by default, so the sync-only core has no async runtime
+/// dependency unless you opt in.
+#[cfg(any(feature = "rt-tokio", feature = "rt-async-std"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "rt-tokio", feature = "rt-async-std")))
+)]
 pub mod async_input;
 
+/// Runtime-abstraction layer selecting between the `rt-tokio` and
+/// `rt-async-std` backends. See [`runtime::race`] and [`runtime::timeout`].
+#[cfg(any(feature = "rt-tokio", feature = "rt-async-std"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "rt-tokio", feature = "rt-async-std")))
+)]
+pub mod runtime;
+
 
 /// Thread-safe synchronous input processing module
 ///
-/// Provides synchronized access to stdin across multiple threads.
-/// Uses internal mutexes to prevent data races while maintaining
-/// blocking behavior expected in synchronous contexts.
+/// Provides synchronized access to stdin across multiple threads via a
+/// dedicated [`InputActor`](thread_safe_input::InputActor) thread, rather
+/// than a shared mutex.
 ///
 /// # Features
-/// - Mutex-protected stdin access
-/// - Blocking read operations
-/// - Thread-local storage options
-/// - Graceful error handling
+/// - A single background thread owns stdin/stdout
+/// - Callers submit requests and block on a channel reply
+/// - Prompts and responses are never interleaved
+/// - No lock to poison if a caller panics mid-request
 ///
 /// # Examples
-/// ```
-/// use crate::thread_safe_input::ThreadSafeInput;
+/// ```no_run
+/// use macro_input::thread_safe_input::InputActor;
 /// use std::thread;
 ///
-/// let input = ThreadSafeInput::new();
-/// let handle = thread::spawn(move || {
-///     match input.read_line() {
-///         Ok(line) => println!("Thread got: {}", line),
-///         Err(e) => eprintln!("Error: {}", e),
-///     }
+/// let actor = InputActor::spawn();
+/// let value: i32 = actor.request("Enter a number", |input| {
+///     input.parse::<i32>().map_err(|e| e.to_string())
 /// });
-/// handle.join().unwrap();
+/// println!("Got: {}", value);
 /// ```
-///
-/// # Implementation Notes
-/// Uses `std::sync::Mutex` internally with proper poisoning handling.
-/// Consider using `parking_lot` mutexes for better performance in contention-heavy scenarios.
 pub mod thread_safe_input;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn reprompts_on_parse_failure_then_succeeds() {
+        let mut reader = Cursor::new(b"not a number\n42\n".as_slice());
+        let mut writer = Vec::new();
+        let result = read_input::<_, i32, fn(&_), fn(&i32) -> Result<(), String>>(
+            &mut reader,
+            &mut writer,
+            "Enter a number",
+            None,
+            None,
+            None,
+        );
+        assert_eq!(result.unwrap(), 42);
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("Invalid input"));
+    }
+
+    #[test]
+    fn rejects_value_that_fails_validation() {
+        let mut reader = Cursor::new(b"-1\n5\n".as_slice());
+        let mut writer = Vec::new();
+        let result = read_input::<_, i32, fn(&_), _>(
+            &mut reader,
+            &mut writer,
+            "Enter a positive number",
+            None,
+            Some(|val: &i32| {
+                if *val > 0 {
+                    Ok(())
+                } else {
+                    Err("must be positive".to_string())
+                }
+            }),
+            None,
+        );
+        assert_eq!(result.unwrap(), 5);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let mut reader = Cursor::new(b"x\ny\nz\n".as_slice());
+        let mut writer = Vec::new();
+        let result = read_input::<_, i32, fn(&_), fn(&i32) -> Result<(), String>>(
+            &mut reader,
+            &mut writer,
+            "Enter a number",
+            None,
+            None,
+            Some(3),
+        );
+        assert!(matches!(
+            result,
+            Err(InputError::MaxAttemptsExceeded { attempts: 3 })
+        ));
+    }
+
+    #[test]
+    fn returns_eof_on_closed_stream() {
+        let mut reader = Cursor::new(b"".as_slice());
+        let mut writer = Vec::new();
+        let result = read_input::<_, i32, fn(&_), fn(&i32) -> Result<(), String>>(
+            &mut reader,
+            &mut writer,
+            "Enter a number",
+            None,
+            None,
+            None,
+        );
+        assert!(matches!(result, Err(InputError::Eof)));
+    }
+
+    #[test]
+    fn set_output_capture_roundtrip() {
+        let previous = set_output_capture(Some(Vec::new()));
+        {
+            let mut stdout = CapturingStdout;
+            write!(stdout, "hello").unwrap();
+        }
+        let captured = set_output_capture(previous);
+        assert_eq!(captured.unwrap(), b"hello");
+    }
+}
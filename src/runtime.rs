@@ -0,0 +1,65 @@
+//! Runtime-abstraction layer for the async input backend.
+//!
+//! `input_async!` and [`AsyncInput`](crate::async_input::AsyncInput) need
+//! little from an async runtime: "an async buffered stdin that yields
+//! lines", an async stdout to write prompts to, and a way to time a read
+//! out. Both `tokio` and `async-std` expose that same shape
+//! (`BufReader::new(stdin())` plus a `read_line` that can be awaited,
+//! `stdout()` with `write_all`/`flush`, and a `timeout(duration, future)`
+//! free function), so
+//! this module re-exports whichever backend was selected via Cargo feature
+//! under one set of names, and the rest of the crate only ever talks to
+//! `crate::runtime`.
+
+#[cfg(all(feature = "rt-tokio", feature = "rt-async-std"))]
+compile_error!("features `rt-tokio` and `rt-async-std` are mutually exclusive; enable only one");
+
+#[cfg(feature = "rt-tokio")]
+mod backend {
+    pub use tokio::io::{stdin, stdout, AsyncBufReadExt, AsyncWriteExt, BufReader, Stdin, Stdout};
+    pub use tokio::time::{error::Elapsed as TimeoutError, timeout};
+}
+
+#[cfg(feature = "rt-async-std")]
+mod backend {
+    pub use async_std::future::{timeout, TimeoutError};
+    pub use async_std::io::prelude::BufReadExt as AsyncBufReadExt;
+    pub use async_std::io::prelude::WriteExt as AsyncWriteExt;
+    pub use async_std::io::{stdin, stdout, BufReader, Stdin, Stdout};
+}
+
+#[cfg(any(feature = "rt-tokio", feature = "rt-async-std"))]
+pub use backend::*;
+
+/// Which side of a [`race`] resolved first.
+pub enum Racing<A, B> {
+    /// The first future resolved first.
+    Left(A),
+    /// The second future resolved first.
+    Right(B),
+}
+
+/// Drives two futures concurrently and returns whichever resolves first.
+///
+/// This is the one piece of the runtime abstraction that doesn't need a
+/// backend at all: it's written purely against `std::future`, so it works
+/// identically whether the surrounding code is built for `rt-tokio` or
+/// `rt-async-std`. It's what [`AsyncInput::read_line_or`](crate::async_input::AsyncInput::read_line_or)
+/// uses to race a stdin read against a caller-supplied cancellation future.
+pub async fn race<A, B>(
+    fut_a: impl std::future::Future<Output = A>,
+    fut_b: impl std::future::Future<Output = B>,
+) -> Racing<A, B> {
+    let mut fut_a = std::pin::pin!(fut_a);
+    let mut fut_b = std::pin::pin!(fut_b);
+    std::future::poll_fn(move |cx| {
+        if let std::task::Poll::Ready(a) = fut_a.as_mut().poll(cx) {
+            return std::task::Poll::Ready(Racing::Left(a));
+        }
+        if let std::task::Poll::Ready(b) = fut_b.as_mut().poll(cx) {
+            return std::task::Poll::Ready(Racing::Right(b));
+        }
+        std::task::Poll::Pending
+    })
+    .await
+}
@@ -1,35 +1,181 @@
 //! Thread-safe input handling utilities
-use std::sync::{Arc, Mutex};
+use std::any::Any;
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
 
+/// The work an [`InputRequest`] hands to the actor thread: run the
+/// prompt-parse-retry loop against its locked stdin and its stdout and box up
+/// whatever `Result<T, InputError>` it produced.
+type InputRun = Box<dyn FnOnce(&mut dyn BufRead, &mut dyn Write) -> Box<dyn Any + Send> + Send>;
 
-/// Global mutex for synchronizing console I/O across threads
+/// A single request submitted to the [`InputActor`].
 ///
-/// This lock ensures thread-safe access to stdin/stdout when multiple threads
-/// need to interact with the user simultaneously. The mutex is:
-/// - Automatically initialized on first use (via `Lazy`)
-/// - Wrapped in `Arc` for shared ownership
-/// - Protects against interleaved output and input races
+/// `run` receives the actor thread's locked stdin and its stdout, performs
+/// the full prompt-parse-retry loop, and boxes the resulting value so it can
+/// travel back over `reply` without `InputRequest` itself needing to be
+/// generic over the value's type.
+struct InputRequest {
+    run: InputRun,
+    reply: Sender<Box<dyn Any + Send>>,
+}
+
+/// A dedicated I/O thread that owns stdin/stdout and services input requests
+/// one at a time over a channel.
 ///
-/// # Example
-/// ```
-/// use self::INPUT_LOCK;
-/// use std::thread;
+/// This replaces the old [`Mutex`](std::sync::Mutex)-protected model: instead
+/// of every caller racing for a lock around their own I/O, each caller sends
+/// an [`InputRequest`] and waits for the actor to hand back a parsed,
+/// validated value. Because the actor thread processes one request fully
+/// before picking up the next, prompts and responses can never interleave,
+/// and there is no lock to poison if a caller panics mid-request.
 ///
-/// let handles: Vec<_> = (0..5).map(|i| {
-///     thread::spawn(move || {
-///         let _guard = INPUT_LOCK.lock().unwrap();
-///         println!("Thread {} got the lock", i);
-///         // Safe to do I/O here
-///     })
-/// }).collect();
+/// # Examples
 ///
-/// for handle in handles {
-///     handle.join().unwrap();
-/// }
+/// ```no_run
+/// use macro_input::thread_safe_input::InputActor;
+///
+/// let actor = InputActor::spawn();
+/// let value: i32 = actor.request("Enter a number", |input| {
+///     input.parse::<i32>().map_err(|e| e.to_string())
+/// });
+/// println!("Got: {}", value);
 /// ```
-pub static INPUT_LOCK: once_cell::sync::Lazy<Arc<Mutex<()>>> =
-    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(())));
+pub struct InputActor {
+    sender: Sender<InputRequest>,
+}
+
+impl InputActor {
+    /// Spawns the background I/O thread and returns a handle to it.
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel::<InputRequest>();
+        thread::spawn(move || {
+            let mut stdin = io::stdin().lock();
+            let mut stdout = io::stdout();
+            for request in receiver {
+                let value = (request.run)(&mut stdin, &mut stdout);
+                // The requester may have stopped waiting; that's fine.
+                let _ = request.reply.send(value);
+            }
+        });
+        Self { sender }
+    }
+
+    /// Submits a prompt-and-parse request to the actor and blocks until it
+    /// has been fully serviced.
+    ///
+    /// `parse` is given the trimmed line of input and returns either the
+    /// parsed value or a message to print before re-prompting.
+    ///
+    /// # Panics
+    /// - If the actor's background thread has shut down.
+    /// - If the actor returns a value whose type doesn't match `T` (not
+    ///   possible through this API, since every request is built generic
+    ///   over the same `T` it reads back).
+    pub fn request<T, F>(&self, desc: &str, parse: F) -> T
+    where
+        T: Send + 'static,
+        F: FnMut(&str) -> Result<T, String> + Send + 'static,
+    {
+        self.request_bounded(desc, parse, None::<fn(&T) -> Result<(), String>>, None)
+            .expect("input stream closed unexpectedly")
+    }
+
+    /// Like [`request`](Self::request), but additionally runs `validate`
+    /// after a successful parse (a rejection is re-prompted exactly like a
+    /// parse failure) and gives up after `max_attempts`, if given, instead
+    /// of retrying indefinitely.
+    ///
+    /// # Panics
+    /// - If the actor's background thread has shut down.
+    /// - If the actor returns a value whose type doesn't match `T` (not
+    ///   possible through this API, since every request is built generic
+    ///   over the same `T` it reads back).
+    pub fn request_bounded<T, F, V>(
+        &self,
+        desc: &str,
+        parse: F,
+        validate: Option<V>,
+        max_attempts: Option<usize>,
+    ) -> Result<T, crate::InputError>
+    where
+        T: Send + 'static,
+        F: FnMut(&str) -> Result<T, String> + Send + 'static,
+        V: FnMut(&T) -> Result<(), String> + Send + 'static,
+    {
+        let desc = desc.to_string();
+        let (reply, result_rx) = mpsc::channel();
+        let run: InputRun = Box::new(move |reader, writer| {
+            Box::new(run_bounded_request(
+                reader, writer, &desc, parse, validate, max_attempts,
+            )) as Box<dyn Any + Send>
+        });
+
+        self.sender
+            .send(InputRequest { run, reply })
+            .expect("input actor thread has shut down");
+        *result_rx
+            .recv()
+            .expect("input actor thread has shut down")
+            .downcast::<Result<T, crate::InputError>>()
+            .expect("input actor returned a value of the wrong type")
+    }
+}
+
+/// The prompt-parse-retry loop behind [`InputActor::request_bounded`], pulled
+/// out as a free function so it can be driven directly against an in-memory
+/// reader/writer in tests instead of the actor's real stdin/stdout.
+fn run_bounded_request<T, F, V>(
+    reader: &mut dyn BufRead,
+    writer: &mut dyn Write,
+    desc: &str,
+    mut parse: F,
+    mut validate: Option<V>,
+    max_attempts: Option<usize>,
+) -> Result<T, crate::InputError>
+where
+    F: FnMut(&str) -> Result<T, String>,
+    V: FnMut(&T) -> Result<(), String>,
+{
+    let mut attempts = 0usize;
+    loop {
+        write!(writer, "{}: ", desc).unwrap();
+        writer.flush().unwrap();
+
+        let mut buffer = String::new();
+        match reader.read_line(&mut buffer) {
+            Ok(0) => return Err(crate::InputError::Eof),
+            Err(_) => continue,
+            Ok(_) => {}
+        }
+        attempts += 1;
+
+        let result = parse(buffer.trim()).and_then(|val| {
+            match validate.as_mut().map(|validate| validate(&val)) {
+                None | Some(Ok(())) => Ok(val),
+                Some(Err(msg)) => Err(msg),
+            }
+        });
+        match result {
+            Ok(val) => return Ok(val),
+            Err(msg) => writeln!(writer, "{}", msg).unwrap(),
+        }
+
+        if let Some(max_attempts) = max_attempts {
+            if attempts >= max_attempts {
+                return Err(crate::InputError::MaxAttemptsExceeded { attempts });
+            }
+        }
+    }
+}
 
+/// Returns a handle to the process-wide [`InputActor`], spawning its
+/// background thread on first use.
+pub fn global_actor() -> &'static InputActor {
+    static GLOBAL_INPUT_ACTOR: once_cell::sync::Lazy<InputActor> =
+        once_cell::sync::Lazy::new(InputActor::spawn);
+    &GLOBAL_INPUT_ACTOR
+}
 
 /// Thread-safe macro for reading user input in concurrent applications
 ///
@@ -39,18 +185,19 @@ pub static INPUT_LOCK: once_cell::sync::Lazy<Arc<Mutex<()>>> =
 /// 3. Graceful error recovery
 ///
 /// # Thread Safety Model
-/// - Uses global [`INPUT_LOCK`] to serialize access to stdin/stdout
-/// - Each macro invocation holds the lock for the entire operation
+/// - Sends each request to the process-wide [`InputActor`] returned by
+///   [`global_actor`], which owns stdin/stdout on a single background thread
+/// - Each macro invocation is serviced as one complete request
 /// - Prevents these common threading issues:
 ///   - Interleaved console output
 ///   - Stdin contention
-///   - Race conditions in prompt-response flows
+///   - Lock poisoning from a panicking caller
 ///
 /// # Examples
 ///
 /// ## Basic Multi-threaded Usage
-/// ```rust
-/// use crate::thread_safe_input::safe_input;
+/// ```no_run
+/// use macro_input::safe_input;
 /// use std::thread;
 ///
 /// let mut threads = vec![];
@@ -68,8 +215,8 @@ pub static INPUT_LOCK: once_cell::sync::Lazy<Arc<Mutex<()>>> =
 /// ```
 ///
 /// ## With Error Handling
-/// ```rust
-/// use crate::thread_safe_input::safe_input;
+/// ```no_run
+/// use macro_input::safe_input;
 /// use std::thread;
 ///
 /// thread::spawn(|| {
@@ -82,83 +229,200 @@ pub static INPUT_LOCK: once_cell::sync::Lazy<Arc<Mutex<()>>> =
 /// ```
 ///
 /// # Performance Considerations
-/// - The global lock means only one thread can do I/O at a time
-/// - For high-throughput systems, consider:
-///   - Dedicated I/O thread with channel communication
-///   - Buffering multiple prompts before locking
-/// - Lock is held only during actual I/O operations
-///
-/// # Panics
-/// - If the mutex is poisoned (a thread panicked while holding the lock)
+/// - A single background thread services every request, so throughput is
+///   bounded by how fast a human (or test harness) can respond to prompts
+/// - Unlike a mutex, there's no lock to poison if a caller panics mid-request
+///
+/// ## The `validate:` and `max_attempts:` Arms
+///
+/// Exercised as real macro invocations below (compile-checked by
+/// `cargo test --doc`, but gated behind `if false` so it never actually
+/// spawns an `InputActor` or touches stdin):
+///
+/// ```
+/// use macro_input::safe_input;
+///
+/// fn compiles() {
+///     let mut number: i32 = 0;
+///     if false {
+///         safe_input!(number, "Enter a number", i32; max_attempts: 3);
+///         safe_input!(number, "Enter a positive number", i32; validate: |n: &i32| {
+///             if *n > 0 { Ok(()) } else { Err("must be positive".to_string()) }
+///         }, max_attempts: 3);
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! safe_input {
-    ($field:expr,$desc:expr,$ty:ty,$on_error:expr) => {{
-        use std::io::{self, Write};
+    ($field:expr, $desc:expr, $ty:ty, $on_error:expr) => {{
         use std::str::FromStr;
 
-        let _lock = $crate::INPUT_LOCK.lock().unwrap();
-        loop {
-            print!("{} ({}): ", $desc, stringify!($ty));
-            io::stdout().flush().unwrap();
+        $field = $crate::thread_safe_input::global_actor().request($desc, move |input| {
+            <$ty>::from_str(input).map_err(|e| {
+                let msg = format!(
+                    "Invalid input '{}'. Expected type: {}. Error: {}",
+                    input,
+                    stringify!($ty),
+                    e
+                );
+                $on_error(e);
+                msg
+            })
+        });
+    }};
 
-            let mut buffer = String::new();
-            if let Err(err) = io::stdin().read_line(&mut buffer) {
-                eprintln!("Failed to read line: {}", err);
-                $on_error(err);
-                continue;
-            }
+    ($field:expr, $desc:expr, $ty:ty) => {{
+        use std::str::FromStr;
 
-            let input = buffer.trim();
-            match <$ty>::from_str(input) {
-                Ok(val) => {
-                    $field = val;
-                    break;
-                }
-                Err(e) => {
-                    eprintln!(
+        $field = $crate::thread_safe_input::global_actor().request($desc, move |input| {
+            <$ty>::from_str(input).map_err(|e| {
+                format!(
+                    "Invalid input '{}'. Expected type: {}. Error: {}",
+                    input,
+                    stringify!($ty),
+                    e
+                )
+            })
+        });
+    }};
+
+    ($field:expr, $desc:expr, $ty:ty; validate: $validate:expr, max_attempts: $max:expr) => {{
+        use std::str::FromStr;
+
+        $field = $crate::thread_safe_input::global_actor().request_bounded(
+            $desc,
+            move |input| {
+                <$ty>::from_str(input).map_err(|e| {
+                    format!(
                         "Invalid input '{}'. Expected type: {}. Error: {}",
                         input,
                         stringify!($ty),
                         e
-                    );
-                    $on_error(e);
-                }
-            }
-        }
+                    )
+                })
+            },
+            Some($validate),
+            Some($max),
+        )
+        .expect("input stream closed unexpectedly");
     }};
 
-    ($field:expr, $desc:expr, $ty:ty) => {{
-        use std::io::{self, Write};
+    ($field:expr, $desc:expr, $ty:ty; validate: $validate:expr) => {{
         use std::str::FromStr;
 
-        // Захватываем глобальный мьютекс
-        let _lock = self::INPUT_LOCK.lock().unwrap();
-
-        loop {
-            print!("{} ({}): ", $desc, stringify!($ty));
-            io::stdout().flush().unwrap();
+        $field = $crate::thread_safe_input::global_actor().request_bounded(
+            $desc,
+            move |input| {
+                <$ty>::from_str(input).map_err(|e| {
+                    format!(
+                        "Invalid input '{}'. Expected type: {}. Error: {}",
+                        input,
+                        stringify!($ty),
+                        e
+                    )
+                })
+            },
+            Some($validate),
+            None,
+        )
+        .expect("input stream closed unexpectedly");
+    }};
 
-            let mut buffer = String::new();
-            if let Err(err) = io::stdin().read_line(&mut buffer) {
-                eprintln!("Failed to read line: {}", err);
-                continue;
-            }
+    ($field:expr, $desc:expr, $ty:ty; max_attempts: $max:expr) => {{
+        use std::str::FromStr;
 
-            let input = buffer.trim();
-            match <$ty>::from_str(input) {
-                Ok(val) => {
-                    $field = val;
-                    break;
-                }
-                Err(e) => {
-                    eprintln!(
+        $field = $crate::thread_safe_input::global_actor().request_bounded(
+            $desc,
+            move |input| {
+                <$ty>::from_str(input).map_err(|e| {
+                    format!(
                         "Invalid input '{}'. Expected type: {}. Error: {}",
                         input,
                         stringify!($ty),
                         e
-                    );
-                }
-            }
-        }
+                    )
+                })
+            },
+            None::<fn(&$ty) -> Result<(), String>>,
+            Some($max),
+        )
+        .expect("input stream closed unexpectedly");
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::run_bounded_request;
+    use std::io::Cursor;
+
+    #[test]
+    fn reprompts_on_parse_failure_then_succeeds() {
+        let mut reader = Cursor::new(b"not a number\n42\n".as_slice());
+        let mut writer = Vec::new();
+        let result = run_bounded_request(
+            &mut reader,
+            &mut writer,
+            "Enter a number",
+            |input| input.parse::<i32>().map_err(|e| e.to_string()),
+            None::<fn(&i32) -> Result<(), String>>,
+            None,
+        );
+        assert_eq!(result.unwrap(), 42);
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("invalid digit"));
+    }
+
+    #[test]
+    fn rejects_value_that_fails_validation() {
+        let mut reader = Cursor::new(b"-1\n5\n".as_slice());
+        let mut writer = Vec::new();
+        let result = run_bounded_request(
+            &mut reader,
+            &mut writer,
+            "Enter a positive number",
+            |input| input.parse::<i32>().map_err(|e| e.to_string()),
+            Some(|val: &i32| {
+                if *val > 0 {
+                    Ok(())
+                } else {
+                    Err("must be positive".to_string())
+                }
+            }),
+            None,
+        );
+        assert_eq!(result.unwrap(), 5);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let mut reader = Cursor::new(b"x\ny\nz\n".as_slice());
+        let mut writer = Vec::new();
+        let result = run_bounded_request(
+            &mut reader,
+            &mut writer,
+            "Enter a number",
+            |input| input.parse::<i32>().map_err(|e| e.to_string()),
+            None::<fn(&i32) -> Result<(), String>>,
+            Some(3),
+        );
+        assert!(matches!(
+            result,
+            Err(crate::InputError::MaxAttemptsExceeded { attempts: 3 })
+        ));
+    }
+
+    #[test]
+    fn returns_eof_on_closed_stream() {
+        let mut reader = Cursor::new(b"".as_slice());
+        let mut writer = Vec::new();
+        let result = run_bounded_request(
+            &mut reader,
+            &mut writer,
+            "Enter a number",
+            |input| input.parse::<i32>().map_err(|e| e.to_string()),
+            None::<fn(&i32) -> Result<(), String>>,
+            None,
+        );
+        assert!(matches!(result, Err(crate::InputError::Eof)));
+    }
+}